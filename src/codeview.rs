@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use goblin::pe::PE;
+
+/// The CodeView (RSDS) record embedded in a PE's debug directory, used both
+/// to find the matching PDB on a symbol server and to verify a PDB actually
+/// matches a binary.
+pub(crate) struct CodeViewInfo {
+    guid: [u8; 16],
+    age: u32,
+    pub(crate) pdb_filename: String,
+}
+
+impl CodeViewInfo {
+    /// Reads the CodeView (RSDS) record from `exe_path`'s PE debug directory.
+    pub(crate) fn read(exe_path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = fs::read(exe_path)?;
+        let pe = PE::parse(&bytes)?;
+        let debug_data = pe
+            .debug_data
+            .context("factorio.exe has no PE debug directory")?;
+        let codeview = debug_data
+            .codeview_pdb70_debug_info
+            .context("factorio.exe's debug directory has no CodeView (RSDS) record")?;
+
+        Ok(Self {
+            guid: codeview.signature,
+            age: codeview.age,
+            pdb_filename: String::from_utf8_lossy(codeview.filename)
+                .trim_end_matches('\0')
+                .to_string(),
+        })
+    }
+
+    pub(crate) fn guid(&self) -> [u8; 16] {
+        self.guid
+    }
+
+    pub(crate) fn age(&self) -> u32 {
+        self.age
+    }
+
+    /// Reorders the GUID's first three fields from PE wire order into the
+    /// canonical display order also used by `uuid::Uuid` and PDB info
+    /// streams, so GUIDs from either source can be compared or printed
+    /// consistently.
+    pub(crate) fn guid_display_bytes(&self) -> [u8; 16] {
+        let g = self.guid;
+        [
+            g[3], g[2], g[1], g[0], g[5], g[4], g[7], g[6], g[8], g[9], g[10], g[11], g[12],
+            g[13], g[14], g[15],
+        ]
+    }
+
+    /// Formats the GUID+age the way symbol servers expect the directory
+    /// component of a symbol path: all 32 hex digits of the display-order
+    /// GUID, uppercase with no separators, followed immediately by the age in
+    /// hex (e.g. `factorio.pdb/<this>/factorio.pdb`).
+    pub(crate) fn signature_hex(&self) -> String {
+        let mut hex = String::with_capacity(33);
+        for byte in self.guid_display_bytes() {
+            hex.push_str(&format!("{byte:02X}"));
+        }
+        hex.push_str(&format!("{:X}", self.age));
+        hex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeViewInfo;
+
+    fn info(guid: [u8; 16], age: u32) -> CodeViewInfo {
+        CodeViewInfo {
+            guid,
+            age,
+            pdb_filename: "factorio.pdb".to_string(),
+        }
+    }
+
+    // GUID/age/signature from Microsoft's own symbol server documentation
+    // example (`mfc42u.pdb`, GUID `B90BA194-8C2D-45BB-97B4-623D92F5A5E2`, age
+    // 2), so this is checked against a known-correct oracle rather than just
+    // re-deriving the byte swap by hand.
+    const DOC_EXAMPLE_WIRE_GUID: [u8; 16] = [
+        0x94, 0xa1, 0x0b, 0xb9, 0x2d, 0x8c, 0xbb, 0x45, 0x97, 0xb4, 0x62, 0x3d, 0x92, 0xf5, 0xa5,
+        0xe2,
+    ];
+
+    #[test]
+    fn guid_display_bytes_swaps_the_first_three_fields() {
+        let display_bytes = info(DOC_EXAMPLE_WIRE_GUID, 2).guid_display_bytes();
+
+        assert_eq!(
+            display_bytes,
+            [
+                0xb9, 0x0b, 0xa1, 0x94, 0x8c, 0x2d, 0x45, 0xbb, 0x97, 0xb4, 0x62, 0x3d, 0x92, 0xf5,
+                0xa5, 0xe2,
+            ]
+        );
+    }
+
+    #[test]
+    fn signature_hex_matches_the_symbol_server_path_format() {
+        assert_eq!(
+            info(DOC_EXAMPLE_WIRE_GUID, 2).signature_hex(),
+            "B90BA1948C2D45BB97B4623D92F5A5E22"
+        );
+    }
+}
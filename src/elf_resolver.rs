@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use memmap2::Mmap;
+use object::{Object, ObjectSegment, ObjectSymbol};
+
+use crate::symbol_resolver::SymbolResolver;
+
+/// Linux counterpart of `PdbSymbolResolver`: indexes `factorio`'s ELF symbol
+/// tables instead of a PDB, the way rust-analyzer's dylib loader does
+/// (`Object::parse(&Mmap::map(&file)?)`, then iterate symbols).
+pub(crate) struct ElfSymbolResolver {
+    symbol_addresses: HashMap<String, u64>,
+    /// The load bias: the offset between a symbol's address as recorded in
+    /// the ELF (`st_value`) and where it actually ends up at runtime. For a
+    /// PIE (`ET_DYN`) binary the lowest `PT_LOAD` segment's `p_vaddr` is 0, so
+    /// this is just the mapped base address from `/proc/self/maps`. For a
+    /// non-PIE (`ET_EXEC`) binary `st_value` is already an absolute address,
+    /// so this is 0 and must *not* be the mapped base address, or addresses
+    /// would be doubled.
+    load_bias: u64,
+}
+
+impl ElfSymbolResolver {
+    /// Creates a new `ElfSymbolResolver` instance.
+    ///
+    /// # Arguments
+    /// * `binary_path` - The path to the `factorio` ELF binary on disk.
+    /// * `module_name` - The name of the module to get the base address of.
+    ///
+    /// # Safety
+    /// Reads `/proc/self/maps` to locate `module_name`, so this must only be
+    /// called after the module has been loaded into the current process.
+    pub(crate) unsafe fn new(binary_path: impl AsRef<Path>, module_name: &str) -> Result<Self> {
+        let file = File::open(binary_path)?;
+        let mmap = Mmap::map(&file)?;
+        let object = object::File::parse(&*mmap)?;
+
+        let mut symbol_addresses = HashMap::new();
+        for symbol in object.dynamic_symbols().chain(object.symbols()) {
+            let Ok(name) = symbol.name() else { continue };
+            if !name.is_empty() {
+                symbol_addresses.insert(name.to_string(), symbol.address());
+            }
+        }
+
+        let min_segment_vaddr = object
+            .segments()
+            .map(|segment| segment.address())
+            .min()
+            .unwrap_or(0);
+        let mapped_base_address = Self::get_module_base_address(module_name)?;
+        let load_bias = mapped_base_address - min_segment_vaddr;
+
+        Ok(Self {
+            symbol_addresses,
+            load_bias,
+        })
+    }
+
+    fn get_function_address(&self, function_name: &str) -> Option<u64> {
+        self.symbol_addresses
+            .get(function_name)
+            .copied()
+            .map(|x| self.load_bias + x)
+    }
+
+    fn get_module_base_address(module_name: &str) -> Result<u64> {
+        let maps = std::fs::read_to_string("/proc/self/maps")
+            .context("Failed to read /proc/self/maps")?;
+
+        for line in maps.lines() {
+            if line.ends_with(module_name) {
+                let range = line
+                    .split('-')
+                    .next()
+                    .context("Malformed /proc/self/maps entry")?;
+                return Ok(u64::from_str_radix(range, 16)?);
+            }
+        }
+
+        bail!("Failed to find loaded module `{module_name}` in /proc/self/maps");
+    }
+}
+
+impl SymbolResolver for ElfSymbolResolver {
+    fn resolve(&self, mangled_name: &str) -> Result<Option<(String, u64)>> {
+        Ok(self
+            .get_function_address(mangled_name)
+            .map(|address| (mangled_name.to_string(), address)))
+    }
+}
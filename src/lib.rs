@@ -3,102 +3,75 @@ use anyhow::{bail, Result, Context};
 use libloading::Library;
 use mod_util::mod_list::ModList;
 use mod_util::mod_loader::ModError;
-use pdb::FallibleIterator;
-use pdb::PDB;
 use rivets::RivetsHook;
 use std::collections::HashMap;
-use std::ffi::{CStr, CString};
-use std::fs::File;
 use std::path::{Path, PathBuf};
-use windows::core::PCSTR;
-use windows::Win32::System::LibraryLoader::GetModuleHandleA;
 
-trait AsPcstr {
-    fn as_pcstr(&self) -> PCSTR;
+mod symbol_resolver;
+#[cfg(target_os = "linux")]
+mod elf_resolver;
+#[cfg(target_os = "windows")]
+mod codeview;
+#[cfg(target_os = "windows")]
+mod pdb_resolver;
+#[cfg(target_os = "windows")]
+mod symbol_cache;
+#[cfg(target_os = "windows")]
+mod symbol_download;
+#[cfg(target_os = "windows")]
+mod symbolize;
+
+use symbol_resolver::SymbolResolver;
+
+/// Builds the platform-appropriate `SymbolResolver` for the running Factorio
+/// process: a PDB reader on Windows, an ELF symbol table reader on Linux.
+///
+/// # Arguments
+/// * `write_path` - Where Rivets keeps its `temp/rivets` scratch folder, used
+///   here as the symbol cache directory.
+///
+/// # Safety
+/// This function is unsafe because it inspects the running process to find
+/// the loaded module's base address. Do not call this function in a
+/// threaded context.
+#[cfg(target_os = "windows")]
+unsafe fn build_symbol_resolver(write_path: impl AsRef<Path>) -> Result<Box<dyn SymbolResolver>> {
+    let bin_folder = get_bin_folder()?;
+    let exe_path = bin_folder.join("factorio.exe");
+    let pdb_path = symbol_download::ensure_matching_pdb(&exe_path, &bin_folder)?;
+    let cache_dir = write_path.as_ref().join("temp/rivets");
+    Ok(Box::new(pdb_resolver::PdbSymbolResolver::new(
+        pdb_path,
+        exe_path,
+        "factorio.exe",
+        cache_dir,
+    )?))
 }
 
-impl AsPcstr for CStr {
-    fn as_pcstr(&self) -> PCSTR {
-        PCSTR(self.as_ptr().cast())
-    }
+/// # Safety
+/// See the Windows `build_symbol_resolver` above.
+#[cfg(target_os = "linux")]
+unsafe fn build_symbol_resolver(_write_path: impl AsRef<Path>) -> Result<Box<dyn SymbolResolver>> {
+    let factorio_path = get_bin_folder()?.join("factorio");
+    Ok(Box::new(elf_resolver::ElfSymbolResolver::new(
+        factorio_path,
+        "factorio",
+    )?))
 }
 
-struct PDBCache {
-    symbol_addresses: HashMap<String, u32>,
-    base_address: u64,
-}
-
-impl PDBCache {
-    /// Creates a new `PDBCache` instance.
-    ///
-    /// # Arguments
-    /// * `pdb_path` - The path to the PDB file.
-    /// * `module_name` - The name of the module to get the base address of.
-    ///
-    /// # Safety
-    /// This function is unsafe because it uses the Windows API.
-    /// Do not call this function in a threaded context.
-    unsafe fn new(pdb_path: impl AsRef<Path>, module_name: &str) -> Result<Self> {
-        let file = File::open(pdb_path)?;
-        let mut pdb = PDB::open(file)?;
-        let base_address = Self::get_dll_base_address(module_name)?;
-
-        let mut symbol_addresses = HashMap::new();
-        let symbol_table = pdb.global_symbols()?;
-        let address_map = pdb.address_map()?;
-
-        symbol_table
-            .iter()
-            .for_each(|symbol| match symbol.parse() {
-                Ok(pdb::SymbolData::Public(data)) if data.function => {
-                    let rva = data.offset.to_rva(&address_map).unwrap_or_default();
-                    symbol_addresses.insert(data.name.to_string().into(), rva.0);
-                    Ok(())
-                }
-                Err(e) => Err(e),
-                _ => Ok(()),
-            })?;
-
-        Ok(Self {
-            symbol_addresses,
-            base_address,
-        })
-    }
-
-    fn get_function_address(&self, function_name: &str) -> Option<u64> {
-        self.symbol_addresses
-            .get(function_name)
-            .copied()
-            .map(|x| self.base_address + u64::from(x))
-    }
-
-    unsafe fn get_dll_base_address(module_name: &str) -> Result<u64> {
-        let result = GetModuleHandleA(CString::new(module_name)?.as_pcstr());
-        match result {
-            Ok(handle) => Ok(handle.0 as u64),
-            Err(err) => bail!(err),
-        }
-    }
-
-    /// Injects a detour into a Factorio compiled function.
-    ///
-    /// # Arguments
-    /// * `factorio_path` - The path to the Factorio binary directory.
-    /// * `function_name` - The name of the function to inject the detour into.
-    /// * `hook` - The detour function to inject.
-    /// 
-    /// # Safety
-    /// todo!
-    unsafe fn inject(&self, hook: &RivetsHook) -> Result<()> {
-        let Some(address) = self.get_function_address(hook.mangled_name.as_str())
-        else {
-            bail!("Failed to find address for the following mangled function inside the PDB: {}", hook.mangled_name);
-        };
-
-        (hook.hook)(address)
-            .into_result()
-            .map_err(std::convert::Into::into)
-    }
+/// Injects a detour into a Factorio compiled function at an already-resolved
+/// address.
+///
+/// # Arguments
+/// * `address` - The address of the function to detour, as resolved by a `SymbolResolver`.
+/// * `hook` - The detour function to inject.
+///
+/// # Safety
+/// todo!
+unsafe fn inject(address: u64, hook: &RivetsHook) -> Result<()> {
+    (hook.hook)(address)
+        .into_result()
+        .map_err(std::convert::Into::into)
 }
 
 fn extract_all_mods_libs(
@@ -163,10 +136,16 @@ fn get_bin_folder() -> Result<PathBuf> {
 }
 
 unsafe fn main(read_path: PathBuf, write_path: PathBuf) -> Result<()> {
-    let pdb_path = get_bin_folder()?.join("factorio.pdb");
-    let pdb_cache = PDBCache::new(pdb_path, "factorio.exe")?;
+    let resolver = build_symbol_resolver(&write_path)?;
+    #[cfg(target_os = "windows")]
+    let mut hook_attribution = symbolize::HookAttribution::default();
+
+    // Tracks which mod hooked which resolved address, in load order, so a
+    // second mod detouring the same function is a diagnosable error instead
+    // of silently overwriting or corrupting the first mod's detour.
+    let mut hooked_addresses: HashMap<u64, (String, String)> = HashMap::new();
 
-    for (mod_name, dll_so_file) in extract_all_mods_libs(read_path, write_path)? {
+    for (mod_name, dll_so_file) in extract_all_mods_libs(read_path, write_path.clone())? {
         let dll_so_file = Library::new(dll_so_file)?;
 
         let err_msg = format!("Failed to get rivets_finalize ABI function for mod {mod_name}. Did you forget to call rivets::finalize!()?");
@@ -174,12 +153,56 @@ unsafe fn main(read_path: PathBuf, write_path: PathBuf) -> Result<()> {
             dll_so_file.get(b"rivets_finalize\0").context(err_msg)?;
 
         for hook in get_hooks() {
-            pdb_cache.inject(&hook)?;
+            let Some((canonical_mangled_name, address)) = resolver.resolve(hook.mangled_name.as_str())? else {
+                bail!(
+                    "Failed to find address for the following mangled function: {}",
+                    hook.mangled_name
+                );
+            };
+
+            if let Some((existing_mod_name, existing_symbol)) = hooked_addresses.get(&address) {
+                bail!(
+                    "Mod `{mod_name}` tried to hook `{canonical_mangled_name}` at {address:#x}, but mod `{existing_mod_name}` already hooked `{existing_symbol}` there. Stacking multiple detours on the same function isn't supported yet.",
+                );
+            }
+            hooked_addresses.insert(address, (mod_name.clone(), canonical_mangled_name.clone()));
+
+            #[cfg(target_os = "windows")]
+            hook_attribution.record(&canonical_mangled_name, &mod_name);
+
+            inject(address, &hook)?;
         }
     }
+
+    #[cfg(target_os = "windows")]
+    hook_attribution.save(write_path.join("temp/rivets/hook_attribution.bin"))?;
+
     Ok(())
 }
 
+/// Symbolizes a Factorio crash dump using the same PDB Rivets injected
+/// detours from, reporting `module!symbol+0xoffset` for each return address
+/// that falls inside `factorio.exe` and tagging frames that landed on a
+/// hooked function with the mod that installed it.
+///
+/// # Safety
+/// See `PdbSymbolResolver::new`.
+#[cfg(target_os = "windows")]
+pub unsafe fn symbolize_crash_dump(
+    minidump_path: impl AsRef<Path>,
+    pdb_path: impl AsRef<Path>,
+    exe_path: impl AsRef<Path>,
+    write_path: impl AsRef<Path>,
+) -> Result<String> {
+    let cache_dir = write_path.as_ref().join("temp/rivets");
+    let resolver =
+        pdb_resolver::PdbSymbolResolver::new(pdb_path, &exe_path, "factorio.exe", &cache_dir)?;
+    let hook_attribution =
+        symbolize::HookAttribution::load(cache_dir.join("hook_attribution.bin")).unwrap_or_default();
+
+    symbolize::symbolize(minidump_path, &resolver, &hook_attribution)
+}
+
 // todo: could this be replaced by abi_stable to make it cross platform?
 // todo: realistically, this should return a RRResult<(), RBoxError> however I was lazy.
 // currently it returns Option<String> where the String repersents an error message
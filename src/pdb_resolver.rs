@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use msvc_demangler::{demangle, DemangleFlags};
+use pdb::FallibleIterator;
+use pdb::PDB;
+use uuid::Uuid;
+use windows::core::PCSTR;
+use windows::Win32::System::LibraryLoader::GetModuleHandleA;
+
+use crate::codeview::CodeViewInfo;
+use crate::symbol_cache::{self, CachedSymbols};
+use crate::symbol_resolver::SymbolResolver;
+
+trait AsPcstr {
+    fn as_pcstr(&self) -> PCSTR;
+}
+
+impl AsPcstr for CStr {
+    fn as_pcstr(&self) -> PCSTR {
+        PCSTR(self.as_ptr().cast())
+    }
+}
+
+pub(crate) struct PdbSymbolResolver {
+    symbol_addresses: HashMap<String, u32>,
+    /// Demangled name -> candidate (mangled name, RVA) pairs. A `Vec` because
+    /// a demangled signature can legitimately collide (overloads that differ
+    /// only in a way the demangler collapses), which `resolve` treats as
+    /// ambiguous. Keeping the mangled name alongside the RVA lets `resolve`
+    /// report back which canonical symbol it actually matched.
+    demangled_addresses: HashMap<String, Vec<(String, u32)>>,
+    base_address: u64,
+}
+
+impl PdbSymbolResolver {
+    /// Creates a new `PdbSymbolResolver` instance.
+    ///
+    /// # Arguments
+    /// * `pdb_path` - The path to the PDB file.
+    /// * `exe_path` - The path to the `factorio.exe` the PDB is checked against.
+    /// * `module_name` - The name of the module to get the base address of.
+    /// * `cache_dir` - Where to read/write the on-disk symbol cache.
+    ///
+    /// # Safety
+    /// This function is unsafe because it uses the Windows API.
+    /// Do not call this function in a threaded context.
+    pub(crate) unsafe fn new(
+        pdb_path: impl AsRef<Path>,
+        exe_path: impl AsRef<Path>,
+        module_name: &str,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let file = File::open(pdb_path)?;
+        let mut pdb = PDB::open(file)?;
+
+        Self::verify_matches_exe(&mut pdb, exe_path)?;
+
+        let base_address = Self::get_dll_base_address(module_name)?;
+        let pdb_info = pdb.pdb_information()?;
+        let cache_path = symbol_cache::cache_path(cache_dir, pdb_info.guid, pdb_info.age);
+
+        let CachedSymbols {
+            symbol_addresses,
+            demangled_addresses,
+        } = match symbol_cache::load(&cache_path)? {
+            Some(cached) => cached,
+            None => {
+                let mut symbol_addresses = HashMap::new();
+                let address_map = pdb.address_map()?;
+
+                let symbol_table = pdb.global_symbols()?;
+                symbol_table
+                    .iter()
+                    .for_each(|symbol| match symbol.parse() {
+                        Ok(pdb::SymbolData::Public(data)) if data.function => {
+                            let rva = data.offset.to_rva(&address_map).unwrap_or_default();
+                            symbol_addresses.insert(data.name.to_string().into(), rva.0);
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                        _ => Ok(()),
+                    })?;
+
+                Self::index_module_private_procedures(
+                    &mut pdb,
+                    &address_map,
+                    &mut symbol_addresses,
+                )?;
+
+                let demangled_addresses = Self::index_demangled_names(&symbol_addresses);
+                let cached = CachedSymbols {
+                    symbol_addresses,
+                    demangled_addresses,
+                };
+                symbol_cache::save(&cache_path, &cached)?;
+                cached
+            }
+        };
+
+        Ok(Self {
+            symbol_addresses,
+            demangled_addresses,
+            base_address,
+        })
+    }
+
+    /// Public globals only cover exported functions. Factorio hooks often
+    /// target functions that only show up as `Procedure` symbols inside a
+    /// compilation unit's (module's) own symbol stream, so walk the DBI
+    /// module streams too and merge their procedures into the same map.
+    fn index_module_private_procedures(
+        pdb: &mut PDB<'_, File>,
+        address_map: &pdb::AddressMap<'_>,
+        symbol_addresses: &mut HashMap<String, u32>,
+    ) -> Result<()> {
+        let debug_info = pdb.debug_information()?;
+        let mut modules = debug_info.modules()?;
+
+        while let Some(module) = modules.next()? {
+            let Some(module_info) = pdb.module_info(&module)? else {
+                continue;
+            };
+
+            let mut symbols = module_info.symbols()?;
+            while let Some(symbol) = symbols.next()? {
+                if let Ok(pdb::SymbolData::Procedure(data)) = symbol.parse() {
+                    let rva = data.offset.to_rva(address_map).unwrap_or_default();
+                    symbol_addresses
+                        .entry(data.name.to_string().into())
+                        .or_insert(rva.0);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Demangles every indexed mangled name, building a reverse index from
+    /// human-readable signature to candidate (mangled name, RVA) pairs.
+    fn index_demangled_names(
+        symbol_addresses: &HashMap<String, u32>,
+    ) -> HashMap<String, Vec<(String, u32)>> {
+        let mut demangled_addresses: HashMap<String, Vec<(String, u32)>> = HashMap::new();
+
+        for (mangled_name, &rva) in symbol_addresses {
+            if let Ok(demangled_name) = demangle(mangled_name, DemangleFlags::COMPLETE) {
+                demangled_addresses
+                    .entry(demangled_name)
+                    .or_default()
+                    .push((mangled_name.clone(), rva));
+            }
+        }
+
+        demangled_addresses
+    }
+
+    /// Compares the PDB's `PdbInformation` (GUID + age) against the CodeView
+    /// record embedded in `exe_path`'s PE debug directory, failing loudly
+    /// instead of letting `get_function_address` silently hand back addresses
+    /// that point into the wrong build of `factorio.exe`.
+    fn verify_matches_exe(pdb: &mut PDB<'_, File>, exe_path: impl AsRef<Path>) -> Result<()> {
+        let pdb_info = pdb.pdb_information()?;
+        let codeview = CodeViewInfo::read(exe_path)?;
+
+        let pdb_guid = *pdb_info.guid.as_bytes();
+        let exe_guid = codeview.guid_display_bytes();
+
+        if pdb_guid != exe_guid || pdb_info.age != codeview.age() {
+            bail!(
+                "PDB does not match factorio.exe (expected GUID {} age {}, found {} age {})",
+                Uuid::from_bytes(exe_guid),
+                codeview.age(),
+                Uuid::from_bytes(pdb_guid),
+                pdb_info.age,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Resolves `name` by exact mangled name first, then falls back to
+    /// treating it as a demangled human-readable signature. Bails if the
+    /// demangled signature is ambiguous (matches more than one mangled
+    /// symbol) rather than silently picking one. Returns the canonical
+    /// mangled name alongside the address, since `name` itself may have been
+    /// the demangled form.
+    fn get_function_address(&self, name: &str) -> Result<Option<(String, u64)>> {
+        if let Some(rva) = self.symbol_addresses.get(name) {
+            return Ok(Some((name.to_string(), self.base_address + u64::from(*rva))));
+        }
+
+        match self.demangled_addresses.get(name) {
+            None => Ok(None),
+            Some(candidates) if candidates.len() == 1 => {
+                let (mangled_name, rva) = &candidates[0];
+                Ok(Some((mangled_name.clone(), self.base_address + u64::from(*rva))))
+            }
+            Some(candidates) => bail!(
+                "Demangled signature `{name}` is ambiguous between {} candidate symbols: {:?}",
+                candidates.len(),
+                candidates.iter().map(|(mangled_name, _)| mangled_name).collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    pub(crate) fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    /// Builds a `(rva, name)` index sorted by RVA, for reverse-mapping a
+    /// crash address back to the symbol it falls inside of.
+    pub(crate) fn rva_index(&self) -> Vec<(u32, String)> {
+        let mut index: Vec<_> = self
+            .symbol_addresses
+            .iter()
+            .map(|(name, rva)| (*rva, name.clone()))
+            .collect();
+        index.sort_unstable_by_key(|(rva, _)| *rva);
+        index
+    }
+
+    unsafe fn get_dll_base_address(module_name: &str) -> Result<u64> {
+        let result = GetModuleHandleA(CString::new(module_name)?.as_pcstr());
+        match result {
+            Ok(handle) => Ok(handle.0 as u64),
+            Err(err) => bail!(err),
+        }
+    }
+}
+
+impl SymbolResolver for PdbSymbolResolver {
+    fn resolve(&self, name: &str) -> Result<Option<(String, u64)>> {
+        self.get_function_address(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(
+        symbol_addresses: HashMap<String, u32>,
+        demangled_addresses: HashMap<String, Vec<(String, u32)>>,
+    ) -> PdbSymbolResolver {
+        PdbSymbolResolver {
+            symbol_addresses,
+            demangled_addresses,
+            base_address: 0x1_0000_0000,
+        }
+    }
+
+    #[test]
+    fn resolves_an_exact_mangled_name_without_consulting_the_demangled_index() {
+        let resolver = resolver(HashMap::from([("?foo@@YAXXZ".to_string(), 0x10)]), HashMap::new());
+
+        assert_eq!(
+            resolver.get_function_address("?foo@@YAXXZ").unwrap(),
+            Some(("?foo@@YAXXZ".to_string(), 0x1_0000_0010))
+        );
+    }
+
+    #[test]
+    fn resolves_an_unambiguous_demangled_signature_to_its_canonical_mangled_name() {
+        let resolver = resolver(
+            HashMap::new(),
+            HashMap::from([("void foo(void)".to_string(), vec![("?foo@@YAXXZ".to_string(), 0x10)])]),
+        );
+
+        assert_eq!(
+            resolver.get_function_address("void foo(void)").unwrap(),
+            Some(("?foo@@YAXXZ".to_string(), 0x1_0000_0010))
+        );
+    }
+
+    #[test]
+    fn bails_on_an_ambiguous_demangled_signature() {
+        let resolver = resolver(
+            HashMap::new(),
+            HashMap::from([(
+                "void foo(void)".to_string(),
+                vec![("?foo@@YAXXZ".to_string(), 0x10), ("?foo@@YAXXZ2".to_string(), 0x20)],
+            )]),
+        );
+
+        assert!(resolver.get_function_address("void foo(void)").is_err());
+    }
+
+    #[test]
+    fn returns_none_for_an_unknown_name() {
+        let resolver = resolver(HashMap::new(), HashMap::new());
+
+        assert_eq!(resolver.get_function_address("nonexistent").unwrap(), None);
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Everything `PdbSymbolResolver` needs to resolve hooks without re-parsing
+/// the PDB: the mangled-name index and its demangled-name index, both of
+/// which are expensive enough over Factorio's full symbol table that they
+/// belong in the cache together, not just the mangled half.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CachedSymbols {
+    pub(crate) symbol_addresses: HashMap<String, u32>,
+    pub(crate) demangled_addresses: HashMap<String, Vec<(String, u32)>>,
+}
+
+/// Builds the on-disk cache path for a PDB's symbol table, keyed by the PDB's
+/// own GUID/age. A game update changes that signature, so the cache
+/// invalidates itself automatically by simply naming a different file.
+pub(crate) fn cache_path(cache_dir: impl AsRef<Path>, guid: Uuid, age: u32) -> PathBuf {
+    cache_dir
+        .as_ref()
+        .join(format!("symcache-{guid}-{age}.bin"))
+}
+
+/// Loads previously cached symbol indices, if present.
+pub(crate) fn load(cache_path: impl AsRef<Path>) -> Result<Option<CachedSymbols>> {
+    let cache_path = cache_path.as_ref();
+    if !cache_path.exists() {
+        return Ok(None);
+    }
+
+    let bytes = fs::read(cache_path)
+        .with_context(|| format!("Failed to read symbol cache at {}", cache_path.display()))?;
+    let cached = bincode::deserialize(&bytes)
+        .with_context(|| format!("Failed to parse symbol cache at {}", cache_path.display()))?;
+
+    Ok(Some(cached))
+}
+
+/// Persists `cached` to `cache_path`, creating its parent directory if
+/// necessary.
+pub(crate) fn save(cache_path: impl AsRef<Path>, cached: &CachedSymbols) -> Result<()> {
+    let cache_path = cache_path.as_ref();
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let bytes = bincode::serialize(cached)?;
+    fs::write(cache_path, bytes)
+        .with_context(|| format!("Failed to write symbol cache to {}", cache_path.display()))?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::codeview::CodeViewInfo;
+
+/// Default Microsoft-compatible symbol server. Override with the
+/// `RIVETS_SYMBOL_SERVER` environment variable to point at a private mirror.
+const DEFAULT_SYMBOL_SERVER: &str = "https://msdl.microsoft.com/download/symbols";
+
+/// Ensures the PDB matching `exe_path`'s embedded CodeView signature exists in
+/// `bin_folder`, downloading it from a symbol server if it's missing.
+///
+/// The on-disk path is keyed by the CodeView GUID/age rather than the bare
+/// `factorio.pdb` name: after a game update the signature (and therefore the
+/// path) changes, so a stale PDB from the previous build is never mistaken
+/// for "already present" the way a fixed filename would be.
+///
+/// Returns the path to the (now-present) PDB.
+pub(crate) fn ensure_matching_pdb(
+    exe_path: impl AsRef<Path>,
+    bin_folder: impl AsRef<Path>,
+) -> Result<PathBuf> {
+    let codeview = CodeViewInfo::read(&exe_path)?;
+    let pdb_path = bin_folder.as_ref().join(versioned_pdb_filename(&codeview));
+
+    if pdb_path.exists() {
+        return Ok(pdb_path);
+    }
+
+    let base_url = std::env::var("RIVETS_SYMBOL_SERVER")
+        .unwrap_or_else(|_| DEFAULT_SYMBOL_SERVER.to_string());
+    let url = format!(
+        "{base_url}/{name}/{sig}/{name}",
+        name = codeview.pdb_filename,
+        sig = codeview.signature_hex(),
+    );
+
+    let response = ureq::get(&url)
+        .call()
+        .with_context(|| format!("Failed to download matching PDB from {url}"))?;
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .context("Failed to read PDB response body")?;
+    fs::write(&pdb_path, bytes)
+        .with_context(|| format!("Failed to write downloaded PDB to {}", pdb_path.display()))?;
+
+    Ok(pdb_path)
+}
+
+/// Builds a cache filename that bakes the CodeView signature in, e.g.
+/// `factorio-11111111222233334444555566667777A.pdb`, so a newer game build
+/// (different signature) never resolves to the same path as an older one.
+fn versioned_pdb_filename(codeview: &CodeViewInfo) -> String {
+    let stem = Path::new(&codeview.pdb_filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("factorio");
+
+    format!("{stem}-{}.pdb", codeview.signature_hex())
+}
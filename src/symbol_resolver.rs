@@ -0,0 +1,19 @@
+use anyhow::Result;
+
+/// Resolves a Factorio symbol name to its absolute address inside the
+/// running process.
+///
+/// Implementations own whatever debug format the platform provides (PDB on
+/// Windows, ELF symbol tables on Linux) so the hook-injection pipeline in
+/// `lib.rs` can stay platform-agnostic. `Ok(None)` means the name wasn't
+/// found; `Err` means the name was found but couldn't be resolved uniquely
+/// (e.g. an ambiguous demangled signature on the PDB backend).
+///
+/// On a match, the canonical mangled name is returned alongside the address:
+/// `name` itself when it was already a mangled name, or the mangled name it
+/// demangled from when `name` was a human-readable signature. Callers that
+/// key state off "the symbol a hook targets" (e.g. crash attribution) should
+/// use that canonical name, not the raw `name` they passed in.
+pub(crate) trait SymbolResolver {
+    fn resolve(&self, name: &str) -> Result<Option<(String, u64)>>;
+}
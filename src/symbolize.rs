@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use minidump::{Minidump, MinidumpModuleList, MinidumpThreadList};
+use minidump_common::traits::Module;
+use serde::{Deserialize, Serialize};
+
+use crate::pdb_resolver::PdbSymbolResolver;
+
+/// Which mod, if any, installed a detour on a given mangled symbol name.
+/// Populated at injection time via `record`, persisted alongside the other
+/// `temp/rivets` state so a later crash can attribute a faulting frame to a
+/// specific mod instead of a bare address.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct HookAttribution {
+    mangled_name_to_mod: HashMap<String, String>,
+}
+
+impl HookAttribution {
+    pub(crate) fn record(&mut self, mangled_name: &str, mod_name: &str) {
+        self.mangled_name_to_mod
+            .insert(mangled_name.to_string(), mod_name.to_string());
+    }
+
+    fn mod_for(&self, mangled_name: &str) -> Option<&str> {
+        self.mangled_name_to_mod.get(mangled_name).map(String::as_str)
+    }
+
+    pub(crate) fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let bytes = bincode::serialize(self)?;
+        fs::write(path, bytes)
+            .with_context(|| format!("Failed to write hook attribution to {}", path.display()))
+    }
+
+    pub(crate) fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read hook attribution from {}", path.display()))?;
+        bincode::deserialize(&bytes)
+            .with_context(|| format!("Failed to parse hook attribution at {}", path.display()))
+    }
+}
+
+/// Finds the greatest RVA in `rva_index` that is `<= address`, i.e. the
+/// symbol `address` falls inside of. When several symbols share that RVA, the
+/// first of them in `rva_index` is returned.
+fn lookup_symbol(rva_index: &[(u32, String)], address: u32) -> Option<&(u32, String)> {
+    let index = rva_index.partition_point(|(rva, _)| *rva <= address);
+    let (symbol_rva, _) = rva_index.get(index.checked_sub(1)?)?;
+    let first = rva_index.partition_point(|(rva, _)| rva < symbol_rva);
+    Some(&rva_index[first])
+}
+
+/// Walks every thread stack in `minidump_path`, reverse-maps each candidate
+/// return address that falls inside `factorio.exe` through `resolver`'s
+/// symbol table, and renders `module!symbol+0xoffset` lines, tagging frames
+/// that landed on a hooked function with the mod that hooked it.
+///
+/// Minidump thread records only carry the raw bytes of their stack memory,
+/// not a pre-walked list of return addresses, so this scans every
+/// pointer-aligned 8-byte word in the stack and treats it as a candidate
+/// return address if it falls inside `factorio.exe`'s module range. That's a
+/// heuristic (it will also pick up stale stack data and non-pointer values
+/// that happen to look like one), but it's the same approach minidump-based
+/// stackwalkers fall back to when they don't have CFI to walk frames exactly.
+pub(crate) fn symbolize(
+    minidump_path: impl AsRef<Path>,
+    resolver: &PdbSymbolResolver,
+    hooks: &HookAttribution,
+) -> Result<String> {
+    let dump = Minidump::read_path(minidump_path.as_ref()).context("Failed to read minidump")?;
+    let modules: MinidumpModuleList = dump.get_stream().context("Minidump has no module list")?;
+    let threads: MinidumpThreadList = dump.get_stream().context("Minidump has no thread list")?;
+
+    let factorio_module = modules.module_at_address(resolver.base_address());
+    let module_name = factorio_module
+        .map(|module| module.code_file().into_owned())
+        .unwrap_or_else(|| "factorio.exe".to_string());
+    let module_end = factorio_module.map(|module| resolver.base_address() + module.size());
+
+    let rva_index = resolver.rva_index();
+    let mut report = String::new();
+
+    for thread in &threads.threads {
+        let Some(stack) = &thread.stack else {
+            continue;
+        };
+
+        for word in stack.bytes.chunks_exact(8) {
+            let candidate = u64::from_le_bytes(word.try_into().expect("chunk_exact(8) yields 8 bytes"));
+
+            if candidate < resolver.base_address() {
+                continue;
+            }
+            if module_end.is_some_and(|end| candidate >= end) {
+                continue;
+            }
+
+            let rva = (candidate - resolver.base_address()) as u32;
+            let Some((symbol_rva, name)) = lookup_symbol(&rva_index, rva) else {
+                continue;
+            };
+
+            let offset = rva - symbol_rva;
+            match hooks.mod_for(name) {
+                Some(mod_name) => report.push_str(&format!(
+                    "{module_name}!{name}+0x{offset:x} (hooked by mod `{mod_name}`)\n"
+                )),
+                None => report.push_str(&format!("{module_name}!{name}+0x{offset:x}\n")),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lookup_symbol;
+
+    fn rva_index() -> Vec<(u32, String)> {
+        vec![
+            (0x1000, "foo".to_string()),
+            (0x2000, "bar".to_string()),
+            (0x2000, "bar_alias".to_string()),
+            (0x3000, "baz".to_string()),
+        ]
+    }
+
+    #[test]
+    fn finds_the_symbol_an_address_falls_inside_of() {
+        let index = rva_index();
+
+        assert_eq!(lookup_symbol(&index, 0x1500), Some(&(0x1000, "foo".to_string())));
+        assert_eq!(lookup_symbol(&index, 0x2800), Some(&(0x2000, "bar".to_string())));
+    }
+
+    #[test]
+    fn matches_an_address_exactly_on_a_symbol_boundary() {
+        let index = rva_index();
+
+        assert_eq!(lookup_symbol(&index, 0x3000), Some(&(0x3000, "baz".to_string())));
+    }
+
+    #[test]
+    fn returns_the_first_of_several_symbols_sharing_an_rva() {
+        let index = rva_index();
+
+        assert_eq!(lookup_symbol(&index, 0x2000), Some(&(0x2000, "bar".to_string())));
+    }
+
+    #[test]
+    fn returns_none_for_an_address_before_the_first_symbol() {
+        let index = rva_index();
+
+        assert_eq!(lookup_symbol(&index, 0x500), None);
+    }
+
+    #[test]
+    fn returns_none_for_an_empty_index() {
+        assert_eq!(lookup_symbol(&[], 0x1000), None);
+    }
+}